@@ -0,0 +1,289 @@
+use std::{os::unix::process::CommandExt, path::PathBuf};
+
+use clap::ValueEnum;
+use color_eyre::eyre::{self, Context};
+
+use crate::compute_session_name;
+
+/// Which multiplexer (if any) to hand the chosen project off to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum Backend {
+    Tmux,
+    Zellij,
+    Shell,
+}
+
+impl Backend {
+    /// Detect which multiplexer we're currently running inside, falling
+    /// back to tmux (the original default) when outside either.
+    pub(crate) fn detect() -> Self {
+        if std::env::var("TMUX").is_ok() {
+            Backend::Tmux
+        } else if std::env::var("ZELLIJ").is_ok() {
+            Backend::Zellij
+        } else {
+            Backend::Tmux
+        }
+    }
+}
+
+/// Working directories of `backend`'s currently-open sessions. Only tmux
+/// exposes this today; other backends report no live sessions.
+pub(crate) fn list_session_paths(backend: Backend) -> eyre::Result<Vec<PathBuf>> {
+    match backend {
+        Backend::Tmux => Tmux::list_session_paths(),
+        Backend::Zellij | Backend::Shell => Ok(Vec::new()),
+    }
+}
+
+/// Common surface for handing a chosen project path off to a terminal
+/// multiplexer (or a plain shell). Implementations provide the raw
+/// primitives; [`SessionBackend::activate`] wires them together the same
+/// way regardless of which one is in use.
+pub(crate) trait SessionBackend {
+    /// Whether we're already running inside this backend (e.g. the `TMUX`
+    /// env var being set), which determines switch vs. attach semantics.
+    fn already_inside(&self) -> bool;
+
+    fn session_exists(&self) -> eyre::Result<bool>;
+
+    fn create(&self) -> eyre::Result<()>;
+
+    /// Replace the current process, attaching to a freshly created session.
+    fn attach(&self) -> std::io::Error;
+
+    /// Replace the current process, switching an already-running client to
+    /// this session.
+    fn switch(&self) -> std::io::Error;
+
+    /// Create the session if necessary, then attach or switch to it
+    /// depending on whether we're already inside the multiplexer.
+    fn activate(&self) -> std::io::Error {
+        if self.already_inside() {
+            if self.session_exists().unwrap_or(false) {
+                self.switch()
+            } else {
+                self.create().expect("creating session");
+                self.switch()
+            }
+        } else {
+            self.create().expect("creating session");
+            self.attach()
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Tmux {
+    path: PathBuf,
+    session_name: String,
+}
+
+impl Tmux {
+    pub(crate) fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            path: path.clone(),
+            session_name: compute_session_name(path),
+        }
+    }
+
+    /// Working directories of all currently running tmux sessions, so they
+    /// can be reconciled back into the cache even if they weren't created by
+    /// `listprojects`.
+    pub(crate) fn list_session_paths() -> eyre::Result<Vec<PathBuf>> {
+        let output = std::process::Command::new("tmux")
+            .args(["list-sessions", "-F", "#{session_path}"])
+            .output()
+            .wrap_err("listing tmux sessions")?;
+
+        if !output.status.success() {
+            // no tmux server running yet, i.e. no sessions to report
+            return Ok(Vec::new());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(PathBuf::from)
+            .collect())
+    }
+}
+
+impl SessionBackend for Tmux {
+    fn already_inside(&self) -> bool {
+        std::env::var("TMUX").is_ok()
+    }
+
+    fn session_exists(&self) -> eyre::Result<bool> {
+        let output = std::process::Command::new("tmux")
+            .arg("has-session")
+            .arg("-t")
+            .arg(&self.session_name)
+            .output()
+            .wrap_err("Checking if tmux session exists")?;
+
+        Ok(output.status.success())
+    }
+
+    fn create(&self) -> eyre::Result<()> {
+        std::process::Command::new("tmux")
+            .args([
+                "new-session",
+                "-d",
+                "-s",
+                &self.session_name,
+                "-c",
+                &self.path.display().to_string(),
+            ])
+            .spawn()
+            .wrap_err("creating new session")?;
+        Ok(())
+    }
+
+    fn attach(&self) -> std::io::Error {
+        std::process::Command::new("tmux")
+            .args(["attach-session", "-t", &self.session_name])
+            .exec()
+    }
+
+    fn switch(&self) -> std::io::Error {
+        std::process::Command::new("tmux")
+            .args(["switch-client", "-t", &self.session_name])
+            .exec()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Zellij {
+    path: PathBuf,
+    session_name: String,
+}
+
+impl Zellij {
+    pub(crate) fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self {
+            path: path.clone(),
+            session_name: compute_session_name(path),
+        }
+    }
+}
+
+impl SessionBackend for Zellij {
+    fn already_inside(&self) -> bool {
+        std::env::var("ZELLIJ").is_ok()
+    }
+
+    fn session_exists(&self) -> eyre::Result<bool> {
+        let output = std::process::Command::new("zellij")
+            .args(["list-sessions", "--short"])
+            .output()
+            .wrap_err("Checking if zellij session exists")?;
+
+        let sessions = String::from_utf8_lossy(&output.stdout);
+        Ok(sessions.lines().any(|line| line == self.session_name))
+    }
+
+    fn create(&self) -> eyre::Result<()> {
+        // zellij has no separate create step: `attach --create` below
+        // creates the session implicitly if it doesn't exist yet.
+        Ok(())
+    }
+
+    fn attach(&self) -> std::io::Error {
+        std::process::Command::new("zellij")
+            .args(["attach", "--create", &self.session_name])
+            .current_dir(&self.path)
+            .exec()
+    }
+
+    fn switch(&self) -> std::io::Error {
+        // zellij has no client-switch primitive; re-attaching from inside a
+        // session just detaches the current client and moves it over.
+        self.attach()
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Shell {
+    path: PathBuf,
+}
+
+impl Shell {
+    pub(crate) fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl SessionBackend for Shell {
+    fn already_inside(&self) -> bool {
+        false
+    }
+
+    fn session_exists(&self) -> eyre::Result<bool> {
+        Ok(false)
+    }
+
+    fn create(&self) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    fn attach(&self) -> std::io::Error {
+        let shell = std::env::var("SHELL").unwrap_or_else(|_| "/bin/sh".to_string());
+        std::process::Command::new(shell)
+            .current_dir(&self.path)
+            .exec()
+    }
+
+    fn switch(&self) -> std::io::Error {
+        self.attach()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `TMUX`/`ZELLIJ` are read by `Backend::detect`, so these tests must not
+    // run concurrently with each other (or anything else touching them).
+    use std::sync::Mutex;
+    static ENV_LOCK: Mutex<()> = Mutex::new(());
+
+    fn with_env<T>(vars: &[(&str, Option<&str>)], f: impl FnOnce() -> T) -> T {
+        let _guard = ENV_LOCK.lock().unwrap();
+        for (name, _) in vars {
+            std::env::remove_var(name);
+        }
+        for (name, value) in vars {
+            if let Some(value) = value {
+                std::env::set_var(name, value);
+            }
+        }
+        let result = f();
+        for (name, _) in vars {
+            std::env::remove_var(name);
+        }
+        result
+    }
+
+    #[test]
+    fn detect_prefers_tmux_when_inside_tmux() {
+        let backend = with_env(
+            &[("TMUX", Some("/tmp/tmux-1000/default,1,0")), ("ZELLIJ", None)],
+            Backend::detect,
+        );
+        assert_eq!(backend, Backend::Tmux);
+    }
+
+    #[test]
+    fn detect_finds_zellij_when_not_in_tmux() {
+        let backend = with_env(&[("TMUX", None), ("ZELLIJ", Some("1"))], Backend::detect);
+        assert_eq!(backend, Backend::Zellij);
+    }
+
+    #[test]
+    fn detect_falls_back_to_tmux_outside_either() {
+        let backend = with_env(&[("TMUX", None), ("ZELLIJ", None)], Backend::detect);
+        assert_eq!(backend, Backend::Tmux);
+    }
+}
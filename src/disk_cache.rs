@@ -1,5 +1,5 @@
 use std::{
-    collections::HashSet,
+    collections::HashMap,
     io::Write,
     path::{Path, PathBuf},
     sync::Arc,
@@ -7,6 +7,7 @@ use std::{
 
 use skim::{SkimItem, SkimItemSender};
 
+use crate::markers::MarkerKind;
 use crate::SelectablePath;
 
 fn cache_filename() -> PathBuf {
@@ -19,54 +20,230 @@ fn cache_filename() -> PathBuf {
     cache_dir.join("cache.txt")
 }
 
+fn now_unix() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64
+}
+
+const HOUR: i64 = 60 * 60;
+const DAY: i64 = 24 * HOUR;
+const WEEK: i64 = 7 * DAY;
+
+/// Header written as the first line of the cache file so future format
+/// changes can be detected and old/corrupt files discarded instead of
+/// misread.
+const MAGIC: &str = "listprojects-cache";
+const VERSION: u32 = 1;
+
+/// Per-project bookkeeping: visit stats for frecency ranking, plus which
+/// marker identified it as a project root (for a future VCS/project-type
+/// badge in the picker).
+#[derive(Clone, Copy, Debug, Default)]
+struct Frecency {
+    frequency: u32,
+    last_accessed: i64,
+    marker: MarkerKind,
+}
+
+impl Frecency {
+    fn score(&self) -> f64 {
+        let age = now_unix().saturating_sub(self.last_accessed);
+        let recency_factor = if age <= HOUR {
+            4.0
+        } else if age <= DAY {
+            2.0
+        } else if age <= WEEK {
+            0.5
+        } else {
+            0.25
+        };
+        self.frequency as f64 * recency_factor
+    }
+
+    fn touch(&mut self) {
+        self.frequency += 1;
+        self.last_accessed = now_unix();
+    }
+}
+
 #[derive(Clone)]
 pub struct Cache {
-    items: HashSet<PathBuf>,
+    items: HashMap<PathBuf, Frecency>,
+    /// Whether `Drop` should persist to the real cache file. Only `false`
+    /// for caches built in tests (such as [`Cache::empty`]), so exercising
+    /// the cache in tests can't clobber the user's actual cache.
+    persist_on_drop: bool,
 }
 
 impl Cache {
+    /// An empty, in-memory cache that never touches the real cache file —
+    /// for tests in this crate that need a `Cache` without the side effects
+    /// `new`/`Drop` carry.
+    #[cfg(test)]
+    pub(crate) fn empty() -> Self {
+        Cache {
+            items: HashMap::new(),
+            persist_on_drop: false,
+        }
+    }
+
     pub fn new() -> Self {
         let cache_filename = cache_filename();
         let items = if cache_filename.is_file() {
             let contents =
                 std::fs::read_to_string(&cache_filename).expect("reading cache contents");
-            contents
-                .lines()
-                .map(PathBuf::from)
-                .collect::<HashSet<PathBuf>>()
+            Self::parse_contents(&contents)
         } else {
-            HashSet::new()
+            HashMap::new()
         };
 
-        Cache { items }
+        let mut cache = Cache {
+            items,
+            persist_on_drop: true,
+        };
+        cache.prune();
+        cache
+    }
+
+    /// Parse the on-disk format: a `magic\tversion` header line followed by
+    /// one entry per line. A missing or mismatched header discards the whole
+    /// file rather than misreading it, so format changes upgrade seamlessly.
+    fn parse_contents(contents: &str) -> HashMap<PathBuf, Frecency> {
+        let mut lines = contents.lines();
+        match lines.next() {
+            Some(header) if header == format!("{MAGIC}\t{VERSION}") => {}
+            _ => return HashMap::new(),
+        }
+        lines.filter_map(Self::parse_line).collect()
+    }
+
+    /// Stat each cached path and drop entries whose directory no longer
+    /// exists or no longer contains the marker that originally qualified it,
+    /// so deleted/moved projects stop appearing in the picker. Reports how
+    /// many entries were pruned.
+    pub fn prune(&mut self) {
+        let before = self.items.len();
+        self.items
+            .retain(|path, entry| path.is_dir() && entry.marker.present_in(path));
+        let pruned = before - self.items.len();
+        if pruned > 0 {
+            eprintln!("pruned {pruned} stale cache entries");
+        }
+    }
+
+    fn parse_line(line: &str) -> Option<(PathBuf, Frecency)> {
+        if line.is_empty() {
+            return None;
+        }
+        let mut fields = line.split('\t');
+        let path = PathBuf::from(fields.next()?);
+        let frequency = fields.next().and_then(|f| f.parse().ok()).unwrap_or(0);
+        let last_accessed = fields.next().and_then(|f| f.parse().ok()).unwrap_or(0);
+        let marker = fields
+            .next()
+            .and_then(MarkerKind::from_name)
+            .unwrap_or_default();
+        Some((
+            path,
+            Frecency {
+                frequency,
+                last_accessed,
+                marker,
+            },
+        ))
+    }
+
+    /// Paths sorted by descending frecency score, hottest projects first.
+    fn sorted_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<&PathBuf> = self.items.keys().collect();
+        paths.sort_by(|a, b| {
+            let score_a = self.items[*a].score();
+            let score_b = self.items[*b].score();
+            score_b.partial_cmp(&score_a).unwrap()
+        });
+        paths.into_iter().cloned().collect()
+    }
+
+    /// Cached paths and their frecency scores, hottest first. Used by
+    /// `--list` to print the history without launching the picker.
+    pub fn entries_with_scores(&self) -> Vec<(PathBuf, f64)> {
+        self.sorted_paths()
+            .into_iter()
+            .map(|path| {
+                let score = self.items[&path].score();
+                (path, score)
+            })
+            .collect()
     }
 
     pub fn prepopulate_with(&self, tx: SkimItemSender) {
         // Implementation for prepopulating the cache with project names
         eprintln!("prepopulating cache with {} items", self.items.len());
-        for p in &self.items {
-            let item: Arc<dyn SkimItem> = Arc::new(SelectablePath { path: p.clone() });
+        for p in self.sorted_paths() {
+            let item: Arc<dyn SkimItem> = Arc::new(SelectablePath { path: p });
             let _ = tx.send(item);
         }
     }
 
     /// Add an item to the cache if not already present, and return true if the cache was updated
     pub fn add_to_cache(&mut self, value: impl Into<PathBuf>) -> bool {
-        self.items.insert(value.into())
+        self.add_to_cache_with_marker(value, MarkerKind::default())
+    }
+
+    /// Add an item to the cache, recording which marker qualified it as a
+    /// project root. Returns true if the cache was updated.
+    pub fn add_to_cache_with_marker(
+        &mut self,
+        value: impl Into<PathBuf>,
+        marker: MarkerKind,
+    ) -> bool {
+        match self.items.entry(value.into()) {
+            std::collections::hash_map::Entry::Occupied(_) => false,
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(Frecency {
+                    marker,
+                    ..Frecency::default()
+                });
+                true
+            }
+        }
+    }
+
+    /// Record a visit to `path`, bumping its frequency and last-accessed time
+    /// so it ranks higher next time.
+    pub fn record_visit(&mut self, path: impl Into<PathBuf>) {
+        self.items.entry(path.into()).or_default().touch();
+    }
+
+    /// Remove an entry from the cache, returning true if it was present.
+    pub fn remove_from_cache(&mut self, path: impl AsRef<Path>) -> bool {
+        self.items.remove(path.as_ref()).is_some()
     }
 
     pub fn save(&self) -> Result<(), std::io::Error> {
         // Implementation for saving the cache to disk
-        self.save_items(self.items.iter().cloned(), cache_filename());
+        self.save_items(cache_filename());
         Ok(())
     }
 
-    fn save_items(&self, items: impl Iterator<Item = PathBuf>, output_path: impl AsRef<Path>) {
-        let items: Vec<_> = items.collect();
-        eprintln!("saving {} items", items.len());
+    fn save_items(&self, output_path: impl AsRef<Path>) {
+        let paths = self.sorted_paths();
+        eprintln!("saving {} items", paths.len());
         let mut f = std::fs::File::create(output_path).expect("creating cache file");
-        for item in items {
-            writeln!(f, "{}", item.display()).expect("writing item to cache file");
+        writeln!(f, "{MAGIC}\t{VERSION}").expect("writing cache header");
+        for path in paths {
+            let frecency = &self.items[&path];
+            writeln!(
+                f,
+                "{}\t{}\t{}\t{}",
+                path.display(),
+                frecency.frequency,
+                frecency.last_accessed,
+                frecency.marker
+            )
+            .expect("writing item to cache file");
         }
         f.flush().expect("flushing cache file");
     }
@@ -74,7 +251,117 @@ impl Cache {
 
 impl Drop for Cache {
     fn drop(&mut self) {
+        if !self.persist_on_drop {
+            return;
+        }
         eprintln!("persisting cache to disk");
         self.save().expect("Failed to save cache");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache_with(items: HashMap<PathBuf, Frecency>) -> Cache {
+        Cache {
+            items,
+            persist_on_drop: false,
+        }
+    }
+
+    #[test]
+    fn score_follows_recency_buckets() {
+        let fresh = Frecency {
+            frequency: 2,
+            last_accessed: now_unix(),
+            marker: MarkerKind::Git,
+        };
+        let stale = Frecency {
+            frequency: 2,
+            last_accessed: now_unix() - WEEK - 1,
+            marker: MarkerKind::Git,
+        };
+        assert_eq!(fresh.score(), 2.0 * 4.0);
+        assert_eq!(stale.score(), 2.0 * 0.25);
+    }
+
+    #[test]
+    fn parse_contents_rejects_missing_header() {
+        let items = Cache::parse_contents("/a/b\t1\t2\tgit\n");
+        assert!(items.is_empty());
+    }
+
+    #[test]
+    fn parse_contents_rejects_wrong_version() {
+        let contents = format!("{MAGIC}\t{}\n/a/b\t1\t2\t.git\n", VERSION + 1);
+        assert!(Cache::parse_contents(&contents).is_empty());
+    }
+
+    #[test]
+    fn parse_contents_round_trips_a_line() {
+        let contents = format!("{MAGIC}\t{VERSION}\n/a/b\t3\t42\t.git\n");
+        let items = Cache::parse_contents(&contents);
+        let entry = items.get(Path::new("/a/b")).unwrap();
+        assert_eq!(entry.frequency, 3);
+        assert_eq!(entry.last_accessed, 42);
+        assert_eq!(entry.marker, MarkerKind::Git);
+    }
+
+    #[test]
+    fn add_to_cache_with_marker_is_idempotent() {
+        let mut cache = cache_with(HashMap::new());
+        assert!(cache.add_to_cache_with_marker("/a/b", MarkerKind::Git));
+        assert!(!cache.add_to_cache_with_marker("/a/b", MarkerKind::CargoToml));
+        assert_eq!(cache.items[Path::new("/a/b")].marker, MarkerKind::Git);
+    }
+
+    #[test]
+    fn record_visit_bumps_frequency_and_timestamp() {
+        let mut cache = cache_with(HashMap::new());
+        cache.record_visit("/a/b");
+        cache.record_visit("/a/b");
+        assert_eq!(cache.items[Path::new("/a/b")].frequency, 2);
+    }
+
+    #[test]
+    fn remove_from_cache_reports_whether_present() {
+        let mut cache = cache_with(HashMap::new());
+        cache.add_to_cache("/a/b");
+        assert!(cache.remove_from_cache("/a/b"));
+        assert!(!cache.remove_from_cache("/a/b"));
+    }
+
+    #[test]
+    fn prune_drops_entries_whose_marker_is_gone() {
+        let tdir = tempfile::tempdir().unwrap();
+        let project = tdir.path().join("project");
+        std::fs::create_dir_all(project.join(".git")).unwrap();
+
+        let mut items = HashMap::new();
+        items.insert(
+            project.clone(),
+            Frecency {
+                marker: MarkerKind::Git,
+                ..Frecency::default()
+            },
+        );
+        items.insert(
+            tdir.path().join("gone"),
+            Frecency {
+                marker: MarkerKind::Git,
+                ..Frecency::default()
+            },
+        );
+        let mut cache = cache_with(items);
+
+        cache.prune();
+
+        assert_eq!(cache.items.len(), 1);
+        assert!(cache.items.contains_key(&project));
+
+        std::fs::remove_dir_all(project.join(".git")).unwrap();
+        cache.prune();
+        assert!(cache.items.is_empty());
+    }
+}
@@ -1,5 +1,4 @@
 use std::{
-    os::unix::process::CommandExt,
     path::{Path, PathBuf},
     sync::Mutex,
 };
@@ -9,9 +8,14 @@ use color_eyre::eyre::{self, Context, OptionExt};
 use ignore::{WalkBuilder, WalkState};
 use skim::prelude::*;
 
+use crate::backend::{Backend, Shell, SessionBackend, Tmux, Zellij};
 use crate::disk_cache::Cache;
+use crate::markers::MarkerKind;
 
+mod backend;
 mod disk_cache;
+mod markers;
+mod watcher;
 
 /// List all projects
 #[derive(Parser)]
@@ -19,9 +23,33 @@ mod disk_cache;
 struct Args {
     /// Root paths to search (default: ~/dev ~/work)
     root: Option<Vec<PathBuf>>,
+
+    /// Watch the root paths for new/removed projects instead of launching the picker
+    #[arg(long)]
+    watch: bool,
+
+    /// Drop cache entries whose directory no longer exists or no longer contains the marker (.git/.jj/.hg or a language marker) that qualified it, then exit
+    #[arg(long)]
+    prune: bool,
+
+    /// Session backend to use (defaults to auto-detecting tmux/zellij, falling back to tmux)
+    #[arg(long, env = "LISTPROJECTS_BACKEND")]
+    backend: Option<Backend>,
+
+    /// Additional language markers to opt into (beyond .git/.jj/.hg), e.g. `--markers cargo-toml`
+    #[arg(long, value_enum)]
+    markers: Vec<MarkerKind>,
+
+    /// Print the project history (path and frecency score) to stdout and exit, without launching the picker
+    #[arg(long)]
+    list: bool,
+
+    /// Reconcile the cache with the backend's currently-open sessions, then exit
+    #[arg(long)]
+    update: bool,
 }
 
-fn compute_session_name(path: impl AsRef<Path>) -> String {
+pub(crate) fn compute_session_name(path: impl AsRef<Path>) -> String {
     let path = path.as_ref();
     let mut iter = path.components().rev();
     let file = iter.next().unwrap().as_os_str().to_string_lossy();
@@ -29,89 +57,68 @@ fn compute_session_name(path: impl AsRef<Path>) -> String {
     format!("{}/{}", parent, file)
 }
 
-#[derive(Debug)]
-struct Tmux {
-    path: PathBuf,
-    session_name: String,
-}
-
-impl Tmux {
-    fn new(path: impl Into<PathBuf>) -> Self {
-        let path = path.into();
-        Self {
-            path: path.clone(),
-            session_name: compute_session_name(path),
-        }
-    }
-
-    fn activate(&self) -> std::io::Error {
-        if Self::in_tmux_session() {
-            if self.session_exists().unwrap() {
-                self.switch_session()
-            } else {
-                self.create_session().expect("creating session");
-                self.switch_session()
-            }
-        } else {
-            self.create_session().expect("creating session");
-            self.attach_session()
-        }
-    }
-
-    fn in_tmux_session() -> bool {
-        std::env::var("TMUX").is_ok()
+/// Build the concrete [`SessionBackend`] for `path`, honouring an explicit
+/// choice or auto-detecting which multiplexer we're currently inside.
+fn session_backend(choice: Option<Backend>, path: impl Into<PathBuf>) -> Box<dyn SessionBackend> {
+    let path = path.into();
+    match choice.unwrap_or_else(Backend::detect) {
+        Backend::Tmux => Box::new(Tmux::new(path)),
+        Backend::Zellij => Box::new(Zellij::new(path)),
+        Backend::Shell => Box::new(Shell::new(path)),
     }
+}
 
-    fn session_exists(&self) -> eyre::Result<bool> {
-        let output = std::process::Command::new("tmux")
-            .arg("has-session")
-            .arg("-t")
-            .arg(&self.session_name)
-            .output()
-            .wrap_err("Checking if tmux session exists")?;
+fn main() -> eyre::Result<()> {
+    color_eyre::install().wrap_err("Installing color-eyre handler")?;
+    let args = Args::parse();
 
-        Ok(output.status.success())
-    }
+    // `Cache::new` already prunes stale entries on load; `--prune` just
+    // persists that and exits without launching the picker.
+    let cache = Arc::new(Mutex::new(Cache::new()));
 
-    fn switch_session(&self) -> std::io::Error {
-        std::process::Command::new("tmux")
-            .args(["switch-client", "-t", &self.session_name])
-            .exec()
+    if args.prune {
+        cache.lock().unwrap().save()?;
+        return Ok(());
     }
 
-    fn create_session(&self) -> eyre::Result<()> {
-        std::process::Command::new("tmux")
-            .args([
-                "new-session",
-                "-d",
-                "-s",
-                &self.session_name,
-                "-c",
-                &self.path.display().to_string(),
-            ])
-            .spawn()
-            .wrap_err("creating new session")?;
-        Ok(())
+    if args.list {
+        for (path, score) in cache.lock().unwrap().entries_with_scores() {
+            println!("{}\t{:.3}", path.display(), score);
+        }
+        return Ok(());
     }
 
-    fn attach_session(&self) -> std::io::Error {
-        std::process::Command::new("tmux")
-            .args(["attach-session", "-t", &self.session_name])
-            .exec()
+    if args.update {
+        let chosen_backend = args.backend.unwrap_or_else(Backend::detect);
+        let enabled_markers = markers::enabled_markers(&args.markers);
+
+        let mut cache = cache.lock().unwrap();
+        for path in backend::list_session_paths(chosen_backend)? {
+            // Only reconcile sessions whose working directory actually
+            // qualifies as a project root; otherwise a session opened in
+            // e.g. $HOME or /tmp would get added to the cache as a `git`
+            // project until the next prune.
+            let Some(marker) = markers::detect_in_dir(&path, &enabled_markers) else {
+                continue;
+            };
+            cache.add_to_cache_with_marker(path.clone(), marker);
+            cache.record_visit(path);
+        }
+        cache.save()?;
+        return Ok(());
     }
-}
-
-fn main() -> eyre::Result<()> {
-    color_eyre::install().wrap_err("Installing color-eyre handler")?;
-    let args = Args::parse();
-
-    let cache = Arc::new(Mutex::new(Cache::new()));
 
     let home = dirs::home_dir().ok_or_else(|| eyre::eyre!("Calculating home directory"))?;
     let roots = args
         .root
         .unwrap_or_else(|| vec![home.join("dev"), home.join("work")]);
 
+    let enabled_markers = markers::enabled_markers(&args.markers);
+
+    if args.watch {
+        return watcher::watch(&roots, cache, enabled_markers);
+    }
+
     let walker = if roots.len() == 1 {
         WalkBuilder::new(&roots[0])
     } else {
@@ -139,10 +146,12 @@ fn main() -> eyre::Result<()> {
             Box::new({
                 let cache = background_cache.clone();
                 let tx = tx.clone();
+                let enabled_markers = enabled_markers.clone();
 
                 move |entry| {
                     if let Ok(entry) = entry {
                         let path = entry.path();
+
                         if !path.is_dir() {
                             return WalkState::Continue;
                         }
@@ -152,26 +161,29 @@ fn main() -> eyre::Result<()> {
                             || path.ends_with("node_modules")
                             || path.ends_with("venv")
                             || path.ends_with("__pycache__")
-                            || path.extension().is_some_and(|ext| ext == "jj")
                         {
                             return WalkState::Skip;
                         }
 
-                        if !path.ends_with(".git") {
-                            return WalkState::Continue;
-                        }
-
-                        // if path.display().to_string().contains(".git") {
-                        //     return WalkState::Skip;
-                        // }
-
-                        let path = path.parent().unwrap();
-
-                        let pb = path.to_path_buf();
-                        if cache.lock().unwrap().add_to_cache(pb.clone()) {
-                            let item: Arc<dyn SkimItem> =
-                                Arc::new(SelectablePath { path: pb.clone() });
-                            let _ = tx.send(item);
+                        // Check markers on the directory itself (rather than
+                        // matching on the marker's own entry as the walker
+                        // reaches it) so `WalkState::Skip` actually stops
+                        // descent for file-based markers like `Cargo.toml`
+                        // too: returning `Skip` for a file entry is a no-op,
+                        // so nested crates in a workspace would otherwise
+                        // each surface as their own project.
+                        if let Some(marker) = markers::detect_in_dir(path, &enabled_markers) {
+                            let pb = path.to_path_buf();
+                            if cache
+                                .lock()
+                                .unwrap()
+                                .add_to_cache_with_marker(pb.clone(), marker)
+                            {
+                                let item: Arc<dyn SkimItem> =
+                                    Arc::new(SelectablePath { path: pb });
+                                let _ = tx.send(item);
+                            }
+                            return WalkState::Skip;
                         }
                     }
                     WalkState::Continue
@@ -208,7 +220,12 @@ fn main() -> eyre::Result<()> {
         .collect::<Vec<_>>();
     let chosen_path = items.first().unwrap();
 
-    let session = Tmux::new(chosen_path);
+    // record the visit so the chosen project ranks higher next time, then
+    // persist it before handing control over to the multiplexer
+    cache.lock().unwrap().record_visit(chosen_path.clone());
+    cache.lock().unwrap().save().unwrap();
+
+    let session = session_backend(args.backend, chosen_path.clone());
     session.activate();
 
     Ok(())
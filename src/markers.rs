@@ -0,0 +1,159 @@
+use std::path::Path;
+
+use clap::ValueEnum;
+
+/// Which marker identified a directory as a project root. VCS markers are
+/// scanned for unconditionally; language markers are opt-in via `--markers`
+/// since a workspace can contain many nested `Cargo.toml`/`package.json`
+/// files that shouldn't each become their own entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, ValueEnum)]
+pub(crate) enum MarkerKind {
+    Git,
+    Jj,
+    Hg,
+    CargoToml,
+    PackageJson,
+    PyprojectToml,
+}
+
+impl Default for MarkerKind {
+    /// Old cache entries predate marker tracking; treat them as `.git`,
+    /// which was the only marker the walker previously recognised.
+    fn default() -> Self {
+        MarkerKind::Git
+    }
+}
+
+impl MarkerKind {
+    pub(crate) const VCS: [MarkerKind; 3] = [MarkerKind::Git, MarkerKind::Jj, MarkerKind::Hg];
+
+    pub(crate) fn from_name(name: &str) -> Option<MarkerKind> {
+        Self::VCS
+            .into_iter()
+            .chain([
+                MarkerKind::CargoToml,
+                MarkerKind::PackageJson,
+                MarkerKind::PyprojectToml,
+            ])
+            .find(|marker| marker.name() == name)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            MarkerKind::Git => ".git",
+            MarkerKind::Jj => ".jj",
+            MarkerKind::Hg => ".hg",
+            MarkerKind::CargoToml => "Cargo.toml",
+            MarkerKind::PackageJson => "package.json",
+            MarkerKind::PyprojectToml => "pyproject.toml",
+        }
+    }
+
+    /// VCS markers are directories (`.git`, `.jj`, `.hg`); language markers
+    /// are files (`Cargo.toml`, ...) that live inside the project root.
+    fn is_directory_marker(&self) -> bool {
+        Self::VCS.contains(self)
+    }
+
+    /// Whether this marker is still present under `root`, used to prune
+    /// entries whose project root no longer qualifies.
+    pub(crate) fn present_in(&self, root: &Path) -> bool {
+        let marker_path = root.join(self.name());
+        if self.is_directory_marker() {
+            marker_path.is_dir()
+        } else {
+            marker_path.is_file()
+        }
+    }
+}
+
+impl std::fmt::Display for MarkerKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Which of the `enabled` markers (if any) `dir` itself qualifies as a
+/// project root under. Used both by the walker (checked on every directory
+/// it visits, before descending) and when reconciling a live session's
+/// working directory that wasn't discovered by the walker.
+pub(crate) fn detect_in_dir(dir: &Path, enabled: &[MarkerKind]) -> Option<MarkerKind> {
+    enabled.iter().copied().find(|marker| marker.present_in(dir))
+}
+
+/// The full set of markers the walker, `--watch`, and `--update` should all
+/// recognise: every VCS marker unconditionally, plus whichever language
+/// markers the caller opted into via `--markers`. Centralised here so the
+/// app's definition of "project" can't drift between entry points.
+pub(crate) fn enabled_markers(opt_in: &[MarkerKind]) -> Vec<MarkerKind> {
+    let mut markers = MarkerKind::VCS.to_vec();
+    markers.extend(opt_in.iter().copied());
+    markers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_name_round_trips_display() {
+        for marker in MarkerKind::VCS
+            .into_iter()
+            .chain([MarkerKind::CargoToml, MarkerKind::PackageJson, MarkerKind::PyprojectToml])
+        {
+            assert_eq!(MarkerKind::from_name(&marker.to_string()), Some(marker));
+        }
+        assert_eq!(MarkerKind::from_name("nonsense"), None);
+    }
+
+    #[test]
+    fn present_in_directory_marker_requires_a_directory() {
+        let tdir = tempfile::tempdir().unwrap();
+        assert!(!MarkerKind::Git.present_in(tdir.path()));
+
+        std::fs::create_dir(tdir.path().join(".git")).unwrap();
+        assert!(MarkerKind::Git.present_in(tdir.path()));
+
+        // a file named `.git` doesn't count
+        std::fs::remove_dir(tdir.path().join(".git")).unwrap();
+        std::fs::write(tdir.path().join(".git"), "gitdir: ../other").unwrap();
+        assert!(!MarkerKind::Git.present_in(tdir.path()));
+    }
+
+    #[test]
+    fn present_in_file_marker_requires_a_file() {
+        let tdir = tempfile::tempdir().unwrap();
+        assert!(!MarkerKind::CargoToml.present_in(tdir.path()));
+
+        std::fs::write(tdir.path().join("Cargo.toml"), "[package]").unwrap();
+        assert!(MarkerKind::CargoToml.present_in(tdir.path()));
+    }
+
+    #[test]
+    fn detect_in_dir_finds_first_enabled_marker_present() {
+        let tdir = tempfile::tempdir().unwrap();
+        std::fs::write(tdir.path().join("Cargo.toml"), "[package]").unwrap();
+
+        assert_eq!(detect_in_dir(tdir.path(), &MarkerKind::VCS), None);
+        assert_eq!(
+            detect_in_dir(tdir.path(), &[MarkerKind::CargoToml]),
+            Some(MarkerKind::CargoToml)
+        );
+    }
+
+    #[test]
+    fn detect_in_dir_does_not_descend_into_nested_projects() {
+        let tdir = tempfile::tempdir().unwrap();
+        let nested = tdir.path().join("crates/inner");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join("Cargo.toml"), "[package]").unwrap();
+
+        // the outer directory itself has no marker, even though a nested
+        // directory does
+        assert_eq!(detect_in_dir(tdir.path(), &[MarkerKind::CargoToml]), None);
+        assert_eq!(
+            detect_in_dir(&nested, &[MarkerKind::CargoToml]),
+            Some(MarkerKind::CargoToml)
+        );
+    }
+}
@@ -0,0 +1,204 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    time::Duration,
+};
+
+use color_eyre::eyre::{self, Context};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::disk_cache::Cache;
+use crate::markers::{self, MarkerKind};
+
+/// Coalesce bursty events (e.g. from `git checkout`/`rebase`) into a single
+/// pass over the cache.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+fn should_skip(path: &Path) -> bool {
+    path.ends_with(".venv")
+        || path.ends_with("node_modules")
+        || path.ends_with("venv")
+        || path.ends_with("__pycache__")
+}
+
+/// Watch `roots` for directory creation/removal, keeping `cache` in sync
+/// until the process is killed. Runs indefinitely, so this is intended to be
+/// invoked via `listprojects --watch` as a long-lived daemon rather than from
+/// the interactive picker flow. `enabled_markers` is the same set the walker
+/// and `--update` use, so `--watch` doesn't diverge from the rest of the
+/// app's definition of "project".
+pub fn watch(
+    roots: &[PathBuf],
+    cache: Arc<Mutex<Cache>>,
+    enabled_markers: Vec<MarkerKind>,
+) -> eyre::Result<()> {
+    let (tx, rx) = mpsc::channel();
+
+    let mut watcher: RecommendedWatcher =
+        notify::recommended_watcher(move |res: notify::Result<Event>| {
+            if let Ok(event) = res {
+                let _ = tx.send(event);
+            }
+        })
+        .wrap_err("creating filesystem watcher")?;
+
+    for root in roots {
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .wrap_err_with(|| format!("watching {}", root.display()))?;
+    }
+
+    eprintln!("watching {} root path(s) for changes", roots.len());
+
+    let mut pending = Vec::new();
+    loop {
+        match rx.recv_timeout(DEBOUNCE) {
+            Ok(event) => pending.push(event),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                if !pending.is_empty() {
+                    flush(std::mem::take(&mut pending), &cache, &enabled_markers);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    Ok(())
+}
+
+fn flush(events: Vec<Event>, cache: &Arc<Mutex<Cache>>, enabled_markers: &[MarkerKind]) {
+    let mut seen = HashSet::new();
+    let mut dirty = false;
+
+    for event in events {
+        for path in event.paths {
+            if should_skip(&path) || !seen.insert(path.clone()) {
+                continue;
+            }
+            dirty |= handle_event(&event.kind, &path, cache, enabled_markers);
+        }
+    }
+
+    if dirty {
+        cache.lock().unwrap().save().ok();
+    }
+}
+
+/// Returns true if the cache was modified.
+fn handle_event(
+    kind: &EventKind,
+    path: &Path,
+    cache: &Arc<Mutex<Cache>>,
+    enabled_markers: &[MarkerKind],
+) -> bool {
+    match kind {
+        EventKind::Create(_) => {
+            // Either the created directory already qualifies as a project
+            // root (e.g. `mkdir -p foo/.git` in one go), or the created
+            // entry *is* a marker appearing inside an already-known
+            // directory (e.g. `git init` run well after `mkdir foo`, more
+            // than one debounce window apart).
+            let project = if path.is_dir() {
+                markers::detect_in_dir(path, enabled_markers)
+                    .map(|marker| (path.to_path_buf(), marker))
+            } else {
+                None
+            }
+            .or_else(|| {
+                let name = path.file_name()?.to_str()?;
+                let marker = MarkerKind::from_name(name).filter(|m| enabled_markers.contains(m))?;
+                Some((path.parent()?.to_path_buf(), marker))
+            });
+
+            let Some((project_path, marker)) = project else {
+                return false;
+            };
+
+            let added = cache
+                .lock()
+                .unwrap()
+                .add_to_cache_with_marker(project_path.clone(), marker);
+            if added {
+                eprintln!("discovered new project: {}", project_path.display());
+            }
+            added
+        }
+        EventKind::Remove(_) => {
+            let removed = cache.lock().unwrap().remove_from_cache(path);
+            if removed {
+                eprintln!("removed project: {}", path.display());
+            }
+            removed
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_cache() -> Arc<Mutex<Cache>> {
+        Arc::new(Mutex::new(Cache::empty()))
+    }
+
+    const CREATE: EventKind = EventKind::Create(notify::event::CreateKind::Folder);
+
+    #[test]
+    fn create_of_a_directory_already_containing_a_marker_is_detected() {
+        let tdir = tempfile::tempdir().unwrap();
+        let project = tdir.path().join("project");
+        std::fs::create_dir_all(project.join(".git")).unwrap();
+
+        let cache = empty_cache();
+        let added = handle_event(&CREATE, &project, &cache, &MarkerKind::VCS);
+
+        assert!(added);
+        assert_eq!(cache.lock().unwrap().entries_with_scores()[0].0, project);
+    }
+
+    #[test]
+    fn create_of_a_marker_inside_an_already_known_directory_is_detected() {
+        // e.g. `mkdir foo` followed, more than a debounce window later, by
+        // `cd foo && git init`: the create event is for `foo/.git` itself.
+        let tdir = tempfile::tempdir().unwrap();
+        let project = tdir.path().join("project");
+        let git_dir = project.join(".git");
+        std::fs::create_dir_all(&git_dir).unwrap();
+
+        let cache = empty_cache();
+        let added = handle_event(&CREATE, &git_dir, &cache, &MarkerKind::VCS);
+
+        assert!(added);
+        assert_eq!(cache.lock().unwrap().entries_with_scores()[0].0, project);
+    }
+
+    #[test]
+    fn create_of_an_unrelated_directory_is_ignored() {
+        let tdir = tempfile::tempdir().unwrap();
+        let plain = tdir.path().join("not-a-project");
+        std::fs::create_dir_all(&plain).unwrap();
+
+        let cache = empty_cache();
+        let added = handle_event(&CREATE, &plain, &cache, &MarkerKind::VCS);
+
+        assert!(!added);
+    }
+
+    #[test]
+    fn remove_drops_a_known_entry() {
+        let cache = empty_cache();
+        cache.lock().unwrap().add_to_cache("/a/b");
+
+        let removed = handle_event(
+            &EventKind::Remove(notify::event::RemoveKind::Folder),
+            Path::new("/a/b"),
+            &cache,
+            &MarkerKind::VCS,
+        );
+
+        assert!(removed);
+        assert!(cache.lock().unwrap().entries_with_scores().is_empty());
+    }
+}